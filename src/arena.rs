@@ -0,0 +1,202 @@
+//! An arena-backed alternative to the `Box`/`Arc` node storage in the crate
+//! root. Nodes live together in a `generational_arena::Arena` and are
+//! addressed by compact `Index` values instead of separate heap
+//! allocations, which keeps large trees cache-friendlier to traverse and
+//! lets a dropped subtree reclaim its arena slots in bulk. Structural
+//! sharing is tracked with an explicit `refs` count on each node rather than
+//! `Arc`'s automatic refcounting, since an `Index` is `Copy` and carries no
+//! ownership of its own.
+//!
+//! The public surface mirrors the `Node` API (`new`/`insert`/`contains`/
+//! `snapshot`) so callers can pick whichever backend suits them.
+
+extern crate generational_arena;
+
+use self::generational_arena::{Arena, Index};
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Clone, Copy)]
+enum ArenaChild {
+    Some(Index),
+    None,
+}
+
+impl ArenaChild {
+    fn is_none(&self) -> bool {
+        match *self {
+            ArenaChild::None => true,
+            ArenaChild::Some(_) => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ArenaNode<T> {
+    children: [ArenaChild; 2],
+    element: T,
+    refs: usize,
+}
+
+/// A persistent ordered set backed by a shared arena. All versions of a tree
+/// that share structure keep a handle to the same `Arena`; a node's `refs`
+/// count says how many child-links and tree roots across all of those
+/// versions point at it, and is what copy-on-write mutation and bulk
+/// reclamation key off of.
+#[derive(Debug)]
+struct ArenaTree<T>
+    where T: Clone, T: Ord
+{
+    arena: Arc<Mutex<Arena<ArenaNode<T>>>>,
+    root: Index,
+}
+
+impl<T> ArenaTree<T>
+    where T: Clone, T: Ord
+{
+    fn new(element: T) -> Self {
+        let mut arena = Arena::new();
+        let root = arena.insert(ArenaNode {
+            children: [ArenaChild::None, ArenaChild::None],
+            element: element,
+            refs: 1,
+        });
+        ArenaTree {
+            arena: Arc::new(Mutex::new(arena)),
+            root: root,
+        }
+    }
+    fn insert(&mut self, v: T) {
+        let mut arena = self.arena.lock().unwrap();
+        self.root = ArenaTree::insert_at(&mut arena, self.root, v);
+    }
+    fn contains(&self, v: T) -> bool {
+        let arena = self.arena.lock().unwrap();
+        let mut index = self.root;
+        loop {
+            let node = &arena[index];
+            if node.element == v {
+                return true;
+            }
+            let child = if v < node.element { node.children[0] } else { node.children[1] };
+            match child {
+                ArenaChild::Some(next) => index = next,
+                ArenaChild::None => return false,
+            }
+        }
+    }
+    /// Hands out a new handle onto the same shared arena. No nodes are
+    /// copied; the root's `refs` is bumped so the first mutation on either
+    /// handle copies the root (and, lazily, whatever else it touches)
+    /// instead of mutating shared state in place.
+    fn snapshot(&self) -> ArenaTree<T> {
+        let mut arena = self.arena.lock().unwrap();
+        arena[self.root].refs += 1;
+        ArenaTree {
+            arena: self.arena.clone(),
+            root: self.root,
+        }
+    }
+    /// Ensures `index` is referenced by exactly one child-link, cloning it
+    /// into a fresh arena slot (and bumping its children's `refs`, since
+    /// they're now doubly-referenced) if it was shared. Returns the index to
+    /// use going forward, which the caller must store back into whichever
+    /// child-link (or root) it came from.
+    fn make_exclusive(arena: &mut Arena<ArenaNode<T>>, index: Index) -> Index {
+        if arena[index].refs <= 1 {
+            return index;
+        }
+        arena[index].refs -= 1;
+        let mut copy = arena[index].clone();
+        copy.refs = 1;
+        for child in copy.children.iter() {
+            if let ArenaChild::Some(ci) = *child {
+                arena[ci].refs += 1;
+            }
+        }
+        arena.insert(copy)
+    }
+    fn insert_at(arena: &mut Arena<ArenaNode<T>>, index: Index, v: T) -> Index {
+        let index = ArenaTree::make_exclusive(arena, index);
+        if arena[index].element == v {
+            return index;
+        }
+
+        let i = if v < arena[index].element { 0 } else { 1 };
+        let new_child = match arena[index].children[i] {
+            ArenaChild::Some(ci) => ArenaTree::insert_at(arena, ci, v),
+            ArenaChild::None => arena.insert(ArenaNode {
+                children: [ArenaChild::None, ArenaChild::None],
+                element: v,
+                refs: 1,
+            }),
+        };
+        arena[index].children[i] = ArenaChild::Some(new_child);
+        index
+    }
+    /// Decrements `index`'s refcount and, once it drops to zero, recursively
+    /// releases its children and removes it from the arena, reclaiming the
+    /// whole unshared subtree's slots in one pass.
+    fn release(arena: &mut Arena<ArenaNode<T>>, index: Index) {
+        let refs = {
+            let node = &mut arena[index];
+            node.refs -= 1;
+            node.refs
+        };
+        if refs > 0 {
+            return;
+        }
+        let children = arena[index].children;
+        arena.remove(index);
+        for child in children.iter() {
+            if let ArenaChild::Some(ci) = *child {
+                ArenaTree::release(arena, ci);
+            }
+        }
+    }
+}
+
+impl<T> Drop for ArenaTree<T>
+    where T: Clone, T: Ord
+{
+    fn drop(&mut self) {
+        let mut arena = self.arena.lock().unwrap();
+        ArenaTree::release(&mut arena, self.root);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ArenaTree;
+
+    #[test]
+    fn insert_and_contains() {
+        let mut tree = ArenaTree::new(12);
+        tree.insert(15);
+        tree.insert(5);
+        tree.insert(8);
+        tree.insert(22);
+
+        assert!(tree.contains(5));
+        assert!(tree.contains(15));
+        assert!(tree.contains(8));
+        assert!(tree.contains(22));
+        assert!(!tree.contains(13));
+    }
+
+    #[test]
+    fn snapshot_is_isolated_from_later_inserts() {
+        let mut tree = ArenaTree::new(12);
+        tree.insert(15);
+        tree.insert(5);
+
+        let mut snapshot = tree.snapshot();
+        tree.insert(1);
+
+        assert!(tree.contains(1));
+        assert!(!snapshot.contains(1));
+
+        snapshot.insert(99);
+        assert!(snapshot.contains(99));
+        assert!(!tree.contains(99));
+    }
+}
@@ -1,8 +1,13 @@
 
 #![allow(dead_code)]
 
+#[cfg(feature = "arena")]
+mod arena;
+mod map;
+
 use std::boxed::Box;
-use std::sync::Arc;
+use std::cmp::Ordering::{Equal, Greater, Less};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
 
 #[derive(Debug)]
 enum Child<T> {
@@ -47,6 +52,27 @@ struct Node<T>
 {
     children: [Child<Node<T>>; 2],
     element: T,
+    size: usize,
+}
+
+/// Weight-balanced (BB[alpha]) tuning constants: a subtree may be at most
+/// `DELTA` times heavier than its sibling before a rotation is required, and
+/// a double rotation is used instead of a single one when the heavy child's
+/// inner grandchild is at least `GAMMA` times as heavy as its outer grandchild.
+const DELTA: usize = 3;
+const GAMMA: usize = 2;
+
+/// Outcome of calling `Node::remove`.
+#[derive(Debug, PartialEq, Eq)]
+enum Removed {
+    /// The element was present and has been removed.
+    Yes,
+    /// The element was not present.
+    No,
+    /// The element is present, but it's the tree's only remaining element;
+    /// since a `Node` always stores at least one, it can't be spliced out.
+    /// `contains` will still report it as present.
+    OnlyElementKept,
 }
 
 impl<T> Node<T>
@@ -57,6 +83,7 @@ impl<T> Node<T>
         Node {
             children: [Child::None, Child::None],
             element: element,
+            size: 1,
         }
     }
     fn make_shared(&mut self) {
@@ -67,6 +94,13 @@ impl<T> Node<T>
             child.make_shared();
         }
     }
+    fn size_of(child: &Child<Node<T>>) -> usize {
+        match *child {
+            Child::Exclusive(ref b) => b.size,
+            Child::Shared(ref arc) => arc.size,
+            Child::None => 0,
+        }
+    }
     fn insert(&mut self, v: T) {
         if self.element == v {
             return;
@@ -81,6 +115,93 @@ impl<T> Node<T>
                 child.insert(v);
             }
         }
+        self.size = 1 + Node::size_of(&self.children[0]) + Node::size_of(&self.children[1]);
+        self.rebalance();
+    }
+    /// Restores the BB[alpha] weight invariant at this node by rotating if
+    /// one child has grown more than `DELTA` times heavier than the other.
+    /// Rotations clone along the path via `make_exclusive`, so snapshots
+    /// taken before the insert that triggered the rebalance are untouched.
+    fn rebalance(&mut self) {
+        let left = Node::size_of(&self.children[0]);
+        let right = Node::size_of(&self.children[1]);
+        if left + right < 2 {
+            return;
+        }
+
+        if right > DELTA * left {
+            self.children[1].make_exclusive();
+            let needs_double = if let Child::Exclusive(ref child) = self.children[1] {
+                Node::size_of(&child.children[0]) >= GAMMA * Node::size_of(&child.children[1])
+            } else {
+                unreachable!()
+            };
+            if needs_double {
+                self.rotate_right_left();
+            } else {
+                self.rotate_left();
+            }
+        } else if left > DELTA * right {
+            self.children[0].make_exclusive();
+            let needs_double = if let Child::Exclusive(ref child) = self.children[0] {
+                Node::size_of(&child.children[1]) >= GAMMA * Node::size_of(&child.children[0])
+            } else {
+                unreachable!()
+            };
+            if needs_double {
+                self.rotate_left_right();
+            } else {
+                self.rotate_right();
+            }
+        }
+    }
+    /// Promotes the right child to take this node's place, making this node
+    /// its new left child.
+    fn rotate_left(&mut self) {
+        use std::mem;
+
+        self.children[1].make_exclusive();
+        let mut new_root = match mem::replace(&mut self.children[1], Child::None) {
+            Child::Exclusive(b) => b,
+            _ => unreachable!(),
+        };
+        self.children[1] = mem::replace(&mut new_root.children[0], Child::None);
+        self.size = 1 + Node::size_of(&self.children[0]) + Node::size_of(&self.children[1]);
+
+        mem::swap(self, &mut *new_root);
+        self.children[0] = Child::Exclusive(new_root);
+        self.size = 1 + Node::size_of(&self.children[0]) + Node::size_of(&self.children[1]);
+    }
+    /// Promotes the left child to take this node's place, making this node
+    /// its new right child.
+    fn rotate_right(&mut self) {
+        use std::mem;
+
+        self.children[0].make_exclusive();
+        let mut new_root = match mem::replace(&mut self.children[0], Child::None) {
+            Child::Exclusive(b) => b,
+            _ => unreachable!(),
+        };
+        self.children[0] = mem::replace(&mut new_root.children[1], Child::None);
+        self.size = 1 + Node::size_of(&self.children[0]) + Node::size_of(&self.children[1]);
+
+        mem::swap(self, &mut *new_root);
+        self.children[1] = Child::Exclusive(new_root);
+        self.size = 1 + Node::size_of(&self.children[0]) + Node::size_of(&self.children[1]);
+    }
+    fn rotate_right_left(&mut self) {
+        self.children[1].make_exclusive();
+        if let Child::Exclusive(ref mut child) = self.children[1] {
+            child.rotate_right();
+        }
+        self.rotate_left();
+    }
+    fn rotate_left_right(&mut self) {
+        self.children[0].make_exclusive();
+        if let Child::Exclusive(ref mut child) = self.children[0] {
+            child.rotate_left();
+        }
+        self.rotate_right();
     }
     fn contains(&self, v: T) -> bool {
         if self.element == v {
@@ -98,6 +219,340 @@ impl<T> Node<T>
         self.make_shared();
         self.clone()
     }
+    /// Removes `v` from the tree if present. Every node that is rewritten
+    /// (the path down to `v`, plus any nodes a rebalancing rotation moves)
+    /// is first copied via `make_exclusive`, so snapshots taken before the
+    /// call still see the old element.
+    ///
+    /// Because a `Node` always stores at least its own element, removing the
+    /// sole remaining element of a single-node tree can't leave some "empty"
+    /// representation behind; that case is reported as
+    /// `Removed::OnlyElementKept` rather than folded into a plain `false`,
+    /// so callers can't mistake "present but not removable" for "was never
+    /// there" the way an overloaded `bool` would.
+    fn remove(&mut self, v: &T) -> Removed {
+        if self.element == *v {
+            use std::mem;
+
+            return match (self.children[0].is_none(), self.children[1].is_none()) {
+                (true, true) => Removed::OnlyElementKept,
+                (false, true) => {
+                    self.children[0].make_exclusive();
+                    let child = match mem::replace(&mut self.children[0], Child::None) {
+                        Child::Exclusive(b) => *b,
+                        _ => unreachable!(),
+                    };
+                    *self = child;
+                    Removed::Yes
+                }
+                (true, false) => {
+                    self.children[1].make_exclusive();
+                    let child = match mem::replace(&mut self.children[1], Child::None) {
+                        Child::Exclusive(b) => *b,
+                        _ => unreachable!(),
+                    };
+                    *self = child;
+                    Removed::Yes
+                }
+                (false, false) => {
+                    self.element = Node::remove_min(&mut self.children[1]);
+                    self.size = 1 + Node::size_of(&self.children[0]) + Node::size_of(&self.children[1]);
+                    self.rebalance();
+                    Removed::Yes
+                }
+            };
+        }
+
+        let index = if *v < self.element { 0 } else { 1 };
+        let removed = Node::remove_from_child(&mut self.children[index], v);
+        if removed {
+            self.size -= 1;
+            self.rebalance();
+            Removed::Yes
+        } else {
+            Removed::No
+        }
+    }
+    /// Removes `v` from the subtree rooted at `child`, splicing the gap left
+    /// behind. Leaves become `Child::None`, single-child nodes are replaced
+    /// by that child, and two-child nodes are replaced in place by their
+    /// in-order successor.
+    fn remove_from_child(child: &mut Child<Node<T>>, v: &T) -> bool {
+        use std::mem;
+
+        if child.is_none() {
+            return false;
+        }
+        child.make_exclusive();
+
+        let remove_here = match *child {
+            Child::Exclusive(ref node) => node.element == *v,
+            _ => unreachable!(),
+        };
+
+        if !remove_here {
+            return if let Child::Exclusive(ref mut node) = *child {
+                let index = if *v < node.element { 0 } else { 1 };
+                let removed = Node::remove_from_child(&mut node.children[index], v);
+                if removed {
+                    node.size -= 1;
+                    node.rebalance();
+                }
+                removed
+            } else {
+                unreachable!()
+            };
+        }
+
+        let mut node = match mem::replace(child, Child::None) {
+            Child::Exclusive(b) => *b,
+            _ => unreachable!(),
+        };
+
+        *child = match (node.children[0].is_none(), node.children[1].is_none()) {
+            (true, true) => Child::None,
+            (true, false) => mem::replace(&mut node.children[1], Child::None),
+            (false, true) => mem::replace(&mut node.children[0], Child::None),
+            (false, false) => {
+                node.element = Node::remove_min(&mut node.children[1]);
+                node.size = 1 + Node::size_of(&node.children[0]) + Node::size_of(&node.children[1]);
+                node.rebalance();
+                Child::Exclusive(Box::new(node))
+            }
+        };
+        true
+    }
+    /// Removes and returns the leftmost (minimum) element of the subtree
+    /// rooted at `child`, used to find the in-order successor when splicing
+    /// out a two-child node.
+    fn remove_min(child: &mut Child<Node<T>>) -> T {
+        use std::mem;
+
+        child.make_exclusive();
+        let is_leftmost = if let Child::Exclusive(ref node) = *child {
+            node.children[0].is_none()
+        } else {
+            unreachable!()
+        };
+
+        if is_leftmost {
+            let mut node = match mem::replace(child, Child::None) {
+                Child::Exclusive(b) => *b,
+                _ => unreachable!(),
+            };
+            *child = mem::replace(&mut node.children[1], Child::None);
+            node.element
+        } else if let Child::Exclusive(ref mut node) = *child {
+            let element = Node::remove_min(&mut node.children[0]);
+            node.size -= 1;
+            node.rebalance();
+            element
+        } else {
+            unreachable!()
+        }
+    }
+    fn child(&self, index: usize) -> Option<&Node<T>> {
+        match self.children[index] {
+            Child::Exclusive(ref b) => Some(b),
+            Child::Shared(ref arc) => Some(arc),
+            Child::None => None,
+        }
+    }
+    fn collect_into(&self, out: &mut Vec<T>) {
+        if let Some(child) = self.child(0) {
+            child.collect_into(out);
+        }
+        out.push(self.element.clone());
+        if let Some(child) = self.child(1) {
+            child.collect_into(out);
+        }
+    }
+    fn diff(&self, other: &Node<T>) -> TreeDiff<T> {
+        let mut diff = TreeDiff {
+            added: Vec::new(),
+            removed: Vec::new(),
+        };
+        Node::diff_nodes(Some(self), Some(other), &mut diff);
+        diff
+    }
+    fn diff_nodes(a: Option<&Node<T>>, b: Option<&Node<T>>, diff: &mut TreeDiff<T>) {
+        match (a, b) {
+            (None, None) => {}
+            (Some(node), None) => node.collect_into(&mut diff.removed),
+            (None, Some(node)) => node.collect_into(&mut diff.added),
+            (Some(a), Some(b)) => {
+                if a.element == b.element {
+                    // Same key at this position: a rotation can't have
+                    // happened here without changing this node's element,
+                    // so the two child arrays still cover the same value
+                    // ranges position-for-position and can be compared
+                    // (and ptr_eq-skipped) directly, recursing the same way
+                    // one level down.
+                    for i in 0..2 {
+                        if Node::shared_and_identical(&a.children[i], &b.children[i]) {
+                            continue;
+                        }
+                        Node::diff_nodes(a.child(i), b.child(i), diff);
+                    }
+                } else {
+                    // The trees diverge in shape below this point (e.g. a
+                    // rebalancing rotation moved elements around), so
+                    // position no longer lines up with value range.
+                    // Positional comparison would walk unrelated subtrees
+                    // and report garbage here, so fall back to a real set
+                    // difference via a sorted merge of both subtrees'
+                    // in-order elements.
+                    Node::diff_by_merge(a, b, diff);
+                }
+            }
+        }
+    }
+    /// Computes a real set difference between the elements of `a` and `b` by
+    /// merge-walking their sorted in-order iterators. Used as the fallback
+    /// once `diff_nodes` finds the two sides no longer share a common shape,
+    /// where the `Arc::ptr_eq` shortcut can no longer be trusted.
+    fn diff_by_merge(a: &Node<T>, b: &Node<T>, diff: &mut TreeDiff<T>) {
+        let mut a_iter = a.iter().peekable();
+        let mut b_iter = b.iter().peekable();
+        loop {
+            let ordering = match (a_iter.peek(), b_iter.peek()) {
+                (None, None) => break,
+                (Some(_), None) => Less,
+                (None, Some(_)) => Greater,
+                (Some(av), Some(bv)) => av.cmp(bv),
+            };
+            match ordering {
+                Less => diff.removed.push(a_iter.next().unwrap().clone()),
+                Greater => diff.added.push(b_iter.next().unwrap().clone()),
+                Equal => {
+                    a_iter.next();
+                    b_iter.next();
+                }
+            }
+        }
+    }
+    fn shared_and_identical(a: &Child<Node<T>>, b: &Child<Node<T>>) -> bool {
+        match (a, b) {
+            (&Child::Shared(ref a), &Child::Shared(ref b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+    /// Returns an in-order iterator over the elements of this snapshot. The
+    /// iterator holds an explicit O(height) stack of node references rather
+    /// than recursing, and follows both `Child::Exclusive` and
+    /// `Child::Shared` links transparently, so it stays valid even after the
+    /// source tree this snapshot was taken from is mutated further.
+    fn iter(&self) -> Iter<T> {
+        let mut iter = Iter { stack: Vec::new() };
+        iter.push_left_spine(Some(self));
+        iter
+    }
+    /// Returns an in-order iterator over the elements within `bounds`,
+    /// pruning subtrees that fall entirely outside the range by comparing
+    /// against `self.element` instead of visiting them.
+    fn range(&self, bounds: ::std::ops::Range<T>) -> RangeIter<T> {
+        let mut iter = RangeIter {
+            stack: Vec::new(),
+            hi: bounds.end,
+        };
+        iter.push_left_spine_from(Some(self), &bounds.start);
+        iter
+    }
+}
+
+/// An in-order iterator over a `Node` snapshot's elements.
+struct Iter<'a, T>
+    where T: Clone, T: 'a
+{
+    stack: Vec<&'a Node<T>>,
+}
+
+impl<'a, T> Iter<'a, T>
+    where T: Clone, T: Ord
+{
+    fn push_left_spine(&mut self, node: Option<&'a Node<T>>) {
+        let mut node = node;
+        while let Some(n) = node {
+            self.stack.push(n);
+            node = n.child(0);
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T>
+    where T: Clone, T: Ord
+{
+    type Item = &'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        let node = match self.stack.pop() {
+            Some(n) => n,
+            None => return None,
+        };
+        self.push_left_spine(node.child(1));
+        Some(&node.element)
+    }
+}
+
+/// An in-order iterator over the elements of a `Node` snapshot that fall
+/// within a half-open range.
+struct RangeIter<'a, T>
+    where T: Clone, T: 'a
+{
+    stack: Vec<&'a Node<T>>,
+    hi: T,
+}
+
+impl<'a, T> RangeIter<'a, T>
+    where T: Clone, T: Ord
+{
+    /// Descends the left spine from `node`, skipping any node (and its left
+    /// subtree) whose element is below `lo` by following its right child
+    /// instead, so a subtree entirely below the range is never visited.
+    fn push_left_spine_from(&mut self, node: Option<&'a Node<T>>, lo: &T) {
+        let mut node = node;
+        while let Some(n) = node {
+            if n.element < *lo {
+                node = n.child(1);
+            } else {
+                self.stack.push(n);
+                node = n.child(0);
+            }
+        }
+    }
+    fn push_left_spine(&mut self, node: Option<&'a Node<T>>) {
+        let mut node = node;
+        while let Some(n) = node {
+            self.stack.push(n);
+            node = n.child(0);
+        }
+    }
+}
+
+impl<'a, T> Iterator for RangeIter<'a, T>
+    where T: Clone, T: Ord
+{
+    type Item = &'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        let node = match self.stack.pop() {
+            Some(n) => n,
+            None => return None,
+        };
+        if node.element >= self.hi {
+            self.stack.clear();
+            return None;
+        }
+        self.push_left_spine(node.child(1));
+        Some(&node.element)
+    }
+}
+
+/// The elements that distinguish two snapshots of the same tree: those only
+/// present in the first (`removed`) and those only present in the second
+/// (`added`).
+#[derive(Debug)]
+struct TreeDiff<T> {
+    added: Vec<T>,
+    removed: Vec<T>,
 }
 
 impl<T> Clone for Node<T>
@@ -114,13 +569,91 @@ impl<T> Clone for Node<T>
         Node {
             children: [duplicate_child(&self.children[0]), duplicate_child(&self.children[1])],
             element: self.element.clone(),
+            size: self.size,
         }
     }
 }
 
+/// A concurrently-readable, single-writer handle around a `Node`.
+///
+/// `read()` hands out a cheap COW snapshot that any number of readers can
+/// hold and query concurrently: the root is always stored already shared
+/// (`make_shared`'d), so `read()` only takes a brief, non-exclusive read
+/// lock on the root pointer to clone the root node (its element and its
+/// children's `Arc`s), never blocking on other
+/// readers or on a writer that hasn't committed yet. `write()` hands out a
+/// `WriteTransaction` that mutates a private copy of the tree; the mutation
+/// only becomes visible to future `read()`s once `commit()` swaps it into
+/// the shared root, so outstanding readers keep seeing the consistent
+/// version they started with. A single writer at a time is enforced by
+/// holding `writer` for the whole transaction, so two overlapping
+/// transactions can't commit on top of each other and lose an update.
+#[derive(Debug)]
+struct Tree<T>
+    where T: Clone, T: Ord
+{
+    root: RwLock<Arc<Node<T>>>,
+    writer: Mutex<()>,
+}
+
+impl<T> Tree<T>
+    where T: Clone, T: Ord
+{
+    fn new(element: T) -> Self {
+        Tree {
+            root: RwLock::new(Arc::new(Node::new(element))),
+            writer: Mutex::new(()),
+        }
+    }
+    fn read(&self) -> Node<T> {
+        (**self.root.read().unwrap()).clone()
+    }
+    fn write(&self) -> WriteTransaction<T> {
+        let guard = self.writer.lock().unwrap();
+        let node = self.read();
+        WriteTransaction {
+            tree: self,
+            node: node,
+            _writer: guard,
+        }
+    }
+}
+
+/// An in-progress write against a `Tree`, isolated from concurrent readers
+/// and writers until `commit()` is called. Holding `_writer` for as long as
+/// this transaction lives is what linearizes writers: a second `write()`
+/// blocks until this one commits (or is dropped without committing).
+struct WriteTransaction<'a, T>
+    where T: Clone, T: Ord, T: 'a
+{
+    tree: &'a Tree<T>,
+    node: Node<T>,
+    _writer: MutexGuard<'a, ()>,
+}
+
+impl<'a, T> WriteTransaction<'a, T>
+    where T: Clone, T: Ord
+{
+    fn insert(&mut self, v: T) {
+        self.node.insert(v);
+    }
+    fn contains(&self, v: T) -> bool {
+        self.node.contains(v)
+    }
+    /// Atomically swaps the mutated tree into the shared root. Readers that
+    /// already called `read()` keep observing their own snapshot.
+    fn commit(mut self) {
+        self.node.make_shared();
+        *self.tree.root.write().unwrap() = Arc::new(self.node);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use Node;
+    use Removed;
+    use Tree;
+    use std::sync::Arc;
 
     #[test]
     fn clone_works() {
@@ -190,4 +723,268 @@ mod tests {
         assert!(!tree.contains(1));
         assert!(tree2.contains(1));
     }
+
+    #[test]
+    fn diff_finds_added_and_removed() {
+        let mut tree = Node::new(12);
+        tree.insert(15);
+        tree.insert(5);
+        tree.insert(8);
+        tree.insert(22);
+
+        let mut tree2 = tree.snapshot();
+        tree2.insert(1);
+
+        let diff = tree.diff(&tree2);
+        assert_eq!(diff.added, vec![1]);
+        assert!(diff.removed.is_empty());
+
+        let diff = tree2.diff(&tree);
+        assert!(diff.added.is_empty());
+        assert_eq!(diff.removed, vec![1]);
+    }
+
+    #[test]
+    fn diff_of_identical_snapshot_is_empty() {
+        let mut tree = Node::new(12);
+        tree.insert(15);
+        tree.insert(5);
+
+        let tree2 = tree.snapshot();
+        let diff = tree.diff(&tree2);
+        assert!(diff.added.is_empty());
+        assert!(diff.removed.is_empty());
+    }
+
+    #[test]
+    fn diff_is_correct_across_a_rebalancing_rotation() {
+        let mut tree = Node::new(0);
+        for v in 1..20 {
+            tree.insert(v);
+        }
+
+        let a = tree.snapshot();
+        for v in 20..60 {
+            tree.insert(v);
+        }
+
+        // Inserting 40 more elements triggers rebalancing rotations, so `a`
+        // and `tree` no longer share the same shape below the root even
+        // though they agree on every element `a` already had.
+        let diff = a.diff(&tree);
+        assert_eq!(diff.added.len(), 40);
+        assert!(diff.removed.is_empty());
+
+        let diff = tree.diff(&a);
+        assert!(diff.added.is_empty());
+        assert_eq!(diff.removed.len(), 40);
+    }
+
+    #[test]
+    fn tree_read_sees_committed_writes() {
+        let tree = Tree::new(12);
+
+        let mut txn = tree.write();
+        txn.insert(5);
+        txn.insert(22);
+        txn.commit();
+
+        let snapshot = tree.read();
+        assert!(snapshot.contains(5));
+        assert!(snapshot.contains(22));
+    }
+
+    #[test]
+    fn tree_outstanding_readers_are_isolated_from_later_writes() {
+        let tree = Tree::new(12);
+
+        let mut txn = tree.write();
+        txn.insert(5);
+        txn.commit();
+
+        let old_snapshot = tree.read();
+
+        let mut txn2 = tree.write();
+        txn2.insert(22);
+        txn2.commit();
+
+        assert!(!old_snapshot.contains(22));
+        assert!(tree.read().contains(22));
+    }
+
+    #[test]
+    fn tree_write_serializes_concurrent_writers_without_lost_updates() {
+        use std::sync::mpsc;
+        use std::thread;
+
+        let tree = Arc::new(Tree::new(0));
+        let (ready_tx, ready_rx) = mpsc::channel();
+        let (proceed_tx, proceed_rx) = mpsc::channel();
+
+        let writer_tree = tree.clone();
+        let handle = thread::spawn(move || {
+            let mut txn = writer_tree.write();
+            ready_tx.send(()).unwrap();
+            proceed_rx.recv().unwrap();
+            for v in 1..50 {
+                txn.insert(v);
+            }
+            txn.commit();
+        });
+
+        ready_rx.recv().unwrap();
+        proceed_tx.send(()).unwrap();
+
+        // The spawned thread is holding its WriteTransaction open; this
+        // write() must block until that transaction commits rather than
+        // starting from a stale snapshot and clobbering its inserts.
+        let mut txn = tree.write();
+        txn.insert(100);
+        txn.commit();
+
+        handle.join().unwrap();
+
+        let snapshot = tree.read();
+        for v in 1..50 {
+            assert!(snapshot.contains(v));
+        }
+        assert!(snapshot.contains(100));
+    }
+
+    fn height(node: &Node<i32>) -> usize {
+        let left = node.child(0).map_or(0, height);
+        let right = node.child(1).map_or(0, height);
+        1 + left.max(right)
+    }
+
+    #[test]
+    fn inserting_sorted_data_stays_balanced() {
+        let mut tree = Node::new(0);
+        for v in 1..1000 {
+            tree.insert(v);
+        }
+
+        for v in 0..1000 {
+            assert!(tree.contains(v));
+        }
+
+        // A fully unbalanced tree over 1000 sorted inserts would have height
+        // 1000; BB[alpha] balancing should keep it within a small multiple
+        // of log2(1000) (~10).
+        assert!(height(&tree) < 40, "height was {}", height(&tree));
+    }
+
+    #[test]
+    fn remove_deletes_present_elements() {
+        let mut tree = Node::new(12);
+        tree.insert(15);
+        tree.insert(5);
+        tree.insert(8);
+        tree.insert(22);
+
+        assert_eq!(tree.remove(&8), Removed::Yes);
+        assert!(!tree.contains(8));
+        assert!(tree.contains(5));
+        assert!(tree.contains(12));
+        assert!(tree.contains(15));
+        assert!(tree.contains(22));
+
+        assert_eq!(tree.remove(&8), Removed::No);
+    }
+
+    #[test]
+    fn remove_leaves_snapshots_untouched() {
+        let mut tree = Node::new(12);
+        tree.insert(15);
+        tree.insert(5);
+        tree.insert(8);
+        tree.insert(22);
+
+        let mut snapshot = tree.snapshot();
+        assert_eq!(tree.remove(&8), Removed::Yes);
+        assert!(!tree.contains(8));
+        assert!(snapshot.contains(8));
+
+        assert_eq!(snapshot.remove(&15), Removed::Yes);
+        assert!(!snapshot.contains(15));
+        assert!(tree.contains(15));
+    }
+
+    #[test]
+    fn remove_of_the_only_remaining_element_is_kept_not_dropped() {
+        let mut tree = Node::new(12);
+        tree.insert(15);
+        tree.insert(5);
+
+        assert_eq!(tree.remove(&5), Removed::Yes);
+        assert_eq!(tree.remove(&15), Removed::Yes);
+        // The element is still present; this must not be confused with the
+        // `Removed::No` returned for an absent value.
+        assert_eq!(tree.remove(&12), Removed::OnlyElementKept);
+        assert!(tree.contains(12));
+    }
+
+    #[test]
+    fn remove_keeps_all_remaining_elements_reachable_over_many_deletes() {
+        let mut tree = Node::new(0);
+        for v in 1..200 {
+            tree.insert(v);
+        }
+        for v in 0..100 {
+            assert_eq!(tree.remove(&v), Removed::Yes);
+        }
+        for v in 0..100 {
+            assert!(!tree.contains(v));
+        }
+        for v in 100..200 {
+            assert!(tree.contains(v));
+        }
+    }
+
+    #[test]
+    fn iter_yields_elements_in_order() {
+        let mut tree = Node::new(12);
+        tree.insert(15);
+        tree.insert(5);
+        tree.insert(8);
+        tree.insert(22);
+
+        let elements: Vec<i32> = tree.iter().cloned().collect();
+        assert_eq!(elements, vec![5, 8, 12, 15, 22]);
+    }
+
+    #[test]
+    fn iter_stays_valid_after_source_is_mutated() {
+        let mut tree = Node::new(12);
+        tree.insert(15);
+        tree.insert(5);
+
+        let snapshot = tree.snapshot();
+        tree.insert(1);
+        tree.insert(99);
+
+        let elements: Vec<i32> = snapshot.iter().cloned().collect();
+        assert_eq!(elements, vec![5, 12, 15]);
+    }
+
+    #[test]
+    fn range_prunes_elements_outside_bounds() {
+        let mut tree = Node::new(50);
+        for v in &[30, 70, 10, 40, 60, 90, 5, 15, 35, 45, 55, 65, 75, 95] {
+            tree.insert(*v);
+        }
+
+        let elements: Vec<i32> = tree.range(30..70).cloned().collect();
+        assert_eq!(elements, vec![30, 35, 40, 45, 50, 55, 60, 65]);
+    }
+
+    #[test]
+    fn range_with_no_elements_in_bounds_is_empty() {
+        let mut tree = Node::new(50);
+        tree.insert(10);
+        tree.insert(90);
+
+        let elements: Vec<i32> = tree.range(55..60).cloned().collect();
+        assert!(elements.is_empty());
+    }
 }
@@ -0,0 +1,131 @@
+//! A persistent ordered key-value map, built on the same exclusive/shared
+//! copy-on-write child machinery as the root `Node` set, but storing a
+//! `key`/`value` pair per node instead of a single element. Inserting an
+//! already-present key upserts its value by path-copying only the nodes on
+//! the way down to it.
+
+use std::boxed::Box;
+use Child;
+
+#[derive(Debug)]
+struct MapNode<K, V>
+    where K: Clone
+{
+    children: [Child<MapNode<K, V>>; 2],
+    key: K,
+    value: V,
+}
+
+impl<K, V> MapNode<K, V>
+    where K: Clone, K: Ord, V: Clone
+{
+    fn new(key: K, value: V) -> Self {
+        MapNode {
+            children: [Child::None, Child::None],
+            key: key,
+            value: value,
+        }
+    }
+    fn make_shared(&mut self) {
+        for child in self.children.iter_mut() {
+            if let &mut Child::Exclusive(ref mut b) = child {
+                b.make_shared();
+            }
+            child.make_shared();
+        }
+    }
+    /// Inserts `key` with `value`, replacing the existing value if `key` is
+    /// already present (upsert semantics).
+    fn insert(&mut self, key: K, value: V) {
+        if self.key == key {
+            self.value = value;
+            return;
+        }
+
+        let index = if key < self.key { 0 } else { 1 };
+        if let Child::None = self.children[index] {
+            self.children[index] = Child::Exclusive(Box::new(MapNode::new(key, value)));
+        } else {
+            self.children[index].make_exclusive();
+            if let Child::Exclusive(ref mut child) = self.children[index] {
+                child.insert(key, value);
+            }
+        }
+    }
+    fn get(&self, key: &K) -> Option<&V> {
+        if self.key == *key {
+            return Some(&self.value);
+        }
+
+        let index = if *key < self.key { 0 } else { 1 };
+        match self.children[index] {
+            Child::Exclusive(ref b) => b.get(key),
+            Child::Shared(ref arc) => arc.get(key),
+            Child::None => None,
+        }
+    }
+    fn snapshot(&mut self) -> MapNode<K, V> {
+        self.make_shared();
+        self.clone()
+    }
+}
+
+impl<K, V> Clone for MapNode<K, V>
+    where K: Clone, V: Clone
+{
+    fn clone(&self) -> MapNode<K, V> {
+        let duplicate_child = |child: &Child<MapNode<K, V>>| {
+            match child {
+                &Child::Shared(ref c) => Child::Shared(c.clone()),
+                &Child::None => Child::None,
+                &Child::Exclusive(_) => unreachable!(),
+            }
+        };
+        MapNode {
+            children: [duplicate_child(&self.children[0]), duplicate_child(&self.children[1])],
+            key: self.key.clone(),
+            value: self.value.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MapNode;
+
+    #[test]
+    fn insert_and_get() {
+        let mut map = MapNode::new(12, "twelve");
+        map.insert(15, "fifteen");
+        map.insert(5, "five");
+
+        assert_eq!(map.get(&12), Some(&"twelve"));
+        assert_eq!(map.get(&15), Some(&"fifteen"));
+        assert_eq!(map.get(&5), Some(&"five"));
+        assert_eq!(map.get(&13), None);
+    }
+
+    #[test]
+    fn insert_upserts_existing_key() {
+        let mut map = MapNode::new(12, "twelve");
+        map.insert(12, "a dozen");
+
+        assert_eq!(map.get(&12), Some(&"a dozen"));
+    }
+
+    #[test]
+    fn snapshot_is_isolated_from_later_inserts() {
+        let mut map = MapNode::new(12, "twelve");
+        map.insert(5, "five");
+
+        let mut snapshot = map.snapshot();
+        map.insert(12, "a dozen");
+
+        assert_eq!(map.get(&12), Some(&"a dozen"));
+        assert_eq!(snapshot.get(&12), Some(&"twelve"));
+
+        snapshot.insert(1, "one");
+        assert_eq!(snapshot.get(&1), Some(&"one"));
+        assert_eq!(map.get(&1), None);
+    }
+}